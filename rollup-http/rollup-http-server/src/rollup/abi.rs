@@ -0,0 +1,257 @@
+// Copyright Cartesi and individual authors (see AUTHORS)
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Ethereum ABI encoding for building voucher calldata.
+//!
+//! Implements the standard Solidity head/tail encoding scheme: a 4-byte
+//! function selector (the first four bytes of `keccak256` of the canonical
+//! signature, e.g. `"transfer(address,uint256)"`) followed by one 32-byte
+//! head slot per argument. Static arguments are written inline in their
+//! head slot; dynamic arguments (`bytes`, `string`, dynamic arrays) write a
+//! 32-byte byte-offset into the tail, where the tail holds a 32-byte length
+//! followed by the data (or, for arrays, the head/tail encoding of the
+//! elements), right-padded to a multiple of 32 bytes.
+
+use sha3::{Digest, Keccak256};
+
+use super::eth::{Address, U256};
+use super::{RollupError, Voucher};
+
+/// A typed argument ready for ABI head/tail encoding.
+#[derive(Debug, Clone)]
+pub enum AbiValue {
+    Address([u8; 20]),
+    Uint256([u8; 32]),
+    Bool(bool),
+    /// `bytesN`; must hold at most 32 bytes.
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(String),
+    /// A dynamic array; elements are encoded as a nested head/tail block.
+    Array(Vec<AbiValue>),
+}
+
+impl AbiValue {
+    fn is_dynamic(&self) -> bool {
+        matches!(self, AbiValue::Bytes(_) | AbiValue::String(_) | AbiValue::Array(_))
+    }
+
+    /// Encodes the value's 32-byte head word, or `None` for dynamic values,
+    /// whose head instead holds an offset filled in by the caller.
+    fn encode_head(&self) -> Result<Option<[u8; 32]>, RollupError> {
+        match self {
+            AbiValue::Address(address) => {
+                let mut word = [0u8; 32];
+                word[12..].copy_from_slice(address);
+                Ok(Some(word))
+            }
+            AbiValue::Uint256(value) => Ok(Some(*value)),
+            AbiValue::Bool(value) => {
+                let mut word = [0u8; 32];
+                word[31] = *value as u8;
+                Ok(Some(word))
+            }
+            AbiValue::FixedBytes(bytes) => {
+                if bytes.len() > 32 {
+                    return Err(RollupError::new(&format!(
+                        "fixed bytes argument has {} bytes, but bytesN allows at most 32",
+                        bytes.len()
+                    )));
+                }
+                let mut word = [0u8; 32];
+                word[..bytes.len()].copy_from_slice(bytes);
+                Ok(Some(word))
+            }
+            AbiValue::Bytes(_) | AbiValue::String(_) | AbiValue::Array(_) => Ok(None),
+        }
+    }
+
+    /// Encodes the dynamic tail entry: a 32-byte length followed by the
+    /// data (or, for arrays, the head/tail encoding of the elements),
+    /// right-padded to a multiple of 32 bytes.
+    fn encode_tail(&self) -> Result<Vec<u8>, RollupError> {
+        match self {
+            AbiValue::Bytes(bytes) => Ok(encode_length_prefixed(bytes)),
+            AbiValue::String(s) => Ok(encode_length_prefixed(s.as_bytes())),
+            AbiValue::Array(elements) => {
+                let mut tail = word_from_usize(elements.len()).to_vec();
+                tail.extend_from_slice(&encode_args(elements)?);
+                Ok(tail)
+            }
+            AbiValue::Address(_) | AbiValue::Uint256(_) | AbiValue::Bool(_) | AbiValue::FixedBytes(_) => {
+                unreachable!("encode_tail called on a static AbiValue")
+            }
+        }
+    }
+}
+
+fn encode_length_prefixed(data: &[u8]) -> Vec<u8> {
+    let mut tail = word_from_usize(data.len()).to_vec();
+    tail.extend_from_slice(data);
+    tail.resize(32 + round_up_to_word(data.len()), 0);
+    tail
+}
+
+fn word_from_usize(value: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&(value as u64).to_be_bytes());
+    word
+}
+
+fn round_up_to_word(len: usize) -> usize {
+    (len + 31) / 32 * 32
+}
+
+/// Computes the 4-byte selector for a canonical function signature, e.g.
+/// `"transfer(address,uint256)"`.
+pub fn function_selector(signature: &str) -> [u8; 4] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&hash[..4]);
+    selector
+}
+
+/// Encodes `args` as a standalone head/tail block, with no selector. Used
+/// both for top-level function calls and for the nested encoding of array
+/// elements.
+fn encode_args(args: &[AbiValue]) -> Result<Vec<u8>, RollupError> {
+    let tail_base = args.len() * 32;
+    let mut heads = Vec::with_capacity(args.len());
+    let mut tail = Vec::new();
+
+    for arg in args {
+        match arg.encode_head()? {
+            Some(word) => heads.push(word),
+            None => {
+                debug_assert!(arg.is_dynamic());
+                heads.push(word_from_usize(tail_base + tail.len()));
+                tail.extend_from_slice(&arg.encode_tail()?);
+            }
+        }
+    }
+
+    let mut encoded = Vec::with_capacity(tail_base + tail.len());
+    for head in heads {
+        encoded.extend_from_slice(&head);
+    }
+    encoded.extend_from_slice(&tail);
+    Ok(encoded)
+}
+
+/// Encodes a Solidity function call: the 4-byte selector followed by the
+/// head/tail encoding of `args`.
+pub fn encode_function_call(signature: &str, args: &[AbiValue]) -> Result<Vec<u8>, RollupError> {
+    let mut encoded = Vec::new();
+    encoded.extend_from_slice(&function_selector(signature));
+    encoded.extend_from_slice(&encode_args(args)?);
+    Ok(encoded)
+}
+
+impl Voucher {
+    /// Builds a voucher whose payload is the ABI-encoded calldata for
+    /// calling `signature` with `args` on `destination`.
+    pub fn from_call(
+        destination: Address,
+        value: U256,
+        signature: &str,
+        args: &[AbiValue],
+    ) -> Result<Self, RollupError> {
+        let payload = encode_function_call(signature, args)?;
+        Ok(Voucher {
+            destination,
+            value,
+            payload: "0x".to_string() + &hex::encode(payload),
+        })
+    }
+
+    /// Convenience constructor for an ERC-20 `transfer(address,uint256)`
+    /// voucher, so dapp authors don't need to hand-roll the calldata.
+    pub fn erc20_transfer(token: Address, to: Address, amount: U256) -> Result<Self, RollupError> {
+        Voucher::from_call(
+            token,
+            U256([0u8; 32]),
+            "transfer(address,uint256)",
+            &[AbiValue::Address(*to.as_bytes()), AbiValue::Uint256(*amount.as_bytes())],
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn function_selector_matches_known_vector() {
+        // Well-known ERC-20 `transfer(address,uint256)` selector.
+        assert_eq!(function_selector("transfer(address,uint256)"), [0xa9, 0x05, 0x9c, 0xbb]);
+    }
+
+    #[test]
+    fn encodes_static_args_inline() {
+        let mut address = [0u8; 20];
+        address[19] = 0x11;
+        let mut amount = [0u8; 32];
+        amount[31] = 42;
+
+        let encoded =
+            encode_function_call("transfer(address,uint256)", &[AbiValue::Address(address), AbiValue::Uint256(amount)])
+                .unwrap();
+
+        let mut expected = vec![0xa9, 0x05, 0x9c, 0xbb];
+        expected.extend_from_slice(&[0u8; 31]);
+        expected.push(0x11);
+        expected.extend_from_slice(&[0u8; 31]);
+        expected.push(42);
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn encodes_dynamic_bytes_with_offset_and_length() {
+        let encoded = encode_function_call("foo(bytes)", &[AbiValue::Bytes(vec![0xaa, 0xbb, 0xcc])]).unwrap();
+
+        let mut expected = function_selector("foo(bytes)").to_vec();
+        expected.extend_from_slice(&word_from_usize(32)); // offset
+        expected.extend_from_slice(&word_from_usize(3)); // length
+        expected.extend_from_slice(&[0xaa, 0xbb, 0xcc]);
+        expected.extend_from_slice(&[0u8; 29]); // right-padded to a word
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn encodes_dynamic_array_with_length_and_elements() {
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut two = [0u8; 32];
+        two[31] = 2;
+
+        let encoded =
+            encode_function_call("foo(uint256[])", &[AbiValue::Array(vec![AbiValue::Uint256(one), AbiValue::Uint256(two)])])
+                .unwrap();
+
+        let mut expected = function_selector("foo(uint256[])").to_vec();
+        expected.extend_from_slice(&word_from_usize(32)); // offset
+        expected.extend_from_slice(&word_from_usize(2)); // length
+        expected.extend_from_slice(&one);
+        expected.extend_from_slice(&two);
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn fixed_bytes_over_32_bytes_is_rejected() {
+        let result = encode_function_call("foo(bytes32)", &[AbiValue::FixedBytes(vec![0u8; 33])]);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,274 @@
+// Copyright Cartesi and individual authors (see AUTHORS)
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Fixed-width Ethereum value types, replacing the loosely-validated hex
+//! strings previously used for addresses and the voucher `CALL` value.
+
+use std::fmt;
+
+use sha3::{Digest, Keccak256};
+
+/// A 20-byte Ethereum address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Address(pub [u8; 20]);
+
+/// A 32-byte big-endian unsigned integer, as used for the `CALL` value of
+/// an on-chain voucher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U256(pub [u8; 32]);
+
+#[derive(Debug)]
+pub struct ParseHexError {
+    message: String,
+}
+
+impl ParseHexError {
+    fn new(message: &str) -> Self {
+        ParseHexError {
+            message: String::from(message),
+        }
+    }
+}
+
+impl fmt::Display for ParseHexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", &self.message)
+    }
+}
+
+impl std::error::Error for ParseHexError {}
+
+fn decode_padded<const N: usize>(value: &str) -> Result<[u8; N], ParseHexError> {
+    let hex_str = value
+        .strip_prefix("0x")
+        .ok_or_else(|| ParseHexError::new(&format!("expected a 0x-prefixed hex string, got '{}'", value)))?;
+    if hex_str.len() > N * 2 {
+        return Err(ParseHexError::new(&format!(
+            "hex string '{}' does not fit in {} bytes",
+            value, N
+        )));
+    }
+    let padded = format!("{:0>width$}", hex_str, width = N * 2);
+    let bytes = hex::decode(&padded)
+        .map_err(|e| ParseHexError::new(&format!("invalid hex string '{}': {}", value, e)))?;
+    let mut out = [0u8; N];
+    out.copy_from_slice(&bytes);
+    Ok(out)
+}
+
+impl Address {
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        &self.0
+    }
+}
+
+impl TryFrom<&str> for Address {
+    type Error = ParseHexError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let hex_str = value
+            .strip_prefix("0x")
+            .ok_or_else(|| ParseHexError::new(&format!("expected a 0x-prefixed hex string, got '{}'", value)))?;
+        if hex_str.len() != 20 * 2 {
+            return Err(ParseHexError::new(&format!(
+                "expected a 20-byte address, got '{}'",
+                value
+            )));
+        }
+        if !has_valid_checksum(hex_str) {
+            return Err(ParseHexError::new(&format!(
+                "'{}' does not match its EIP-55 checksum",
+                value
+            )));
+        }
+        let bytes = hex::decode(hex_str)
+            .map_err(|e| ParseHexError::new(&format!("invalid hex string '{}': {}", value, e)))?;
+        let mut out = [0u8; 20];
+        out.copy_from_slice(&bytes);
+        Ok(Address(out))
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+/// Renders `lower_hex_digits` (40 lowercase hex characters, no `0x`
+/// prefix) with EIP-55 mixed-case checksumming: an alphabetic hex
+/// character is uppercased exactly when the corresponding nibble of
+/// `keccak256` of the ASCII lowercase string is `>= 8`.
+fn checksum_hex(lower_hex_digits: &str) -> String {
+    let hash = Keccak256::digest(lower_hex_digits.as_bytes());
+    lower_hex_digits
+        .char_indices()
+        .map(|(i, c)| {
+            if !c.is_ascii_alphabetic() {
+                return c;
+            }
+            let nibble = if i % 2 == 0 { hash[i / 2] >> 4 } else { hash[i / 2] & 0x0f };
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// Checks that `hex_digits` (40 hex characters, no `0x` prefix) is either
+/// unchecksummed (all one case) or a correct EIP-55 checksum.
+fn has_valid_checksum(hex_digits: &str) -> bool {
+    let all_lower = !hex_digits.chars().any(|c| c.is_ascii_uppercase());
+    let all_upper = !hex_digits.chars().any(|c| c.is_ascii_lowercase());
+    if all_lower || all_upper {
+        return true;
+    }
+    checksum_hex(&hex_digits.to_ascii_lowercase()) == hex_digits
+}
+
+impl Address {
+    /// Renders the address in EIP-55 mixed-case checksum form.
+    pub fn to_checksum(&self) -> String {
+        format!("0x{}", checksum_hex(&hex::encode(self.0)))
+    }
+}
+
+impl serde::Serialize for Address {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Address {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        Address::try_from(value.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
+impl U256 {
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl TryFrom<&str> for U256 {
+    type Error = ParseHexError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        decode_padded::<32>(value).map(U256)
+    }
+}
+
+impl fmt::Display for U256 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0))
+    }
+}
+
+impl serde::Serialize for U256 {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for U256 {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        U256::try_from(value.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Canonical test vectors from EIP-55.
+    const CHECKSUMMED_ADDRESSES: [&str; 4] = [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn accepts_and_reproduces_known_eip55_checksums() {
+        for checksummed in CHECKSUMMED_ADDRESSES {
+            let address = Address::try_from(checksummed).unwrap();
+            assert_eq!(address.to_checksum(), checksummed);
+        }
+    }
+
+    #[test]
+    fn accepts_all_lowercase_and_all_uppercase_as_unchecksummed() {
+        let checksummed = CHECKSUMMED_ADDRESSES[0];
+        let lower = checksummed.to_ascii_lowercase();
+        let upper = format!("0x{}", checksummed[2..].to_ascii_uppercase());
+        assert!(Address::try_from(lower.as_str()).is_ok());
+        assert!(Address::try_from(upper.as_str()).is_ok());
+    }
+
+    #[test]
+    fn rejects_a_single_flipped_case_character() {
+        let checksummed = CHECKSUMMED_ADDRESSES[0];
+        // Flip the case of the first alphabetic hex digit, breaking the checksum.
+        let mut chars: Vec<char> = checksummed.chars().collect();
+        let flip_at = chars
+            .iter()
+            .position(|c| c.is_ascii_hexdigit() && c.is_ascii_alphabetic())
+            .unwrap();
+        chars[flip_at] = if chars[flip_at].is_ascii_uppercase() {
+            chars[flip_at].to_ascii_lowercase()
+        } else {
+            chars[flip_at].to_ascii_uppercase()
+        };
+        let tampered: String = chars.into_iter().collect();
+        assert!(Address::try_from(tampered.as_str()).is_err());
+    }
+
+    #[test]
+    fn rejects_wrong_length_addresses() {
+        assert!(Address::try_from("0x1234").is_err());
+    }
+
+    #[test]
+    fn u256_left_pads_short_hex_values() {
+        let value = U256::try_from("0x1").unwrap();
+        let mut expected = [0u8; 32];
+        expected[31] = 1;
+        assert_eq!(value.0, expected);
+    }
+
+    #[test]
+    fn u256_rejects_values_longer_than_32_bytes() {
+        let too_long = format!("0x{}", "11".repeat(33));
+        assert!(U256::try_from(too_long.as_str()).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright Cartesi and individual authors (see AUTHORS)
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! HTTP server exposing the rollup device as a REST/JSON API, so dapps
+//! written in any language can drive it without linking against this
+//! crate. Enabled by the `http-server` feature.
+//!
+//! `POST /finish` accepts the previous request's status, blocks for the
+//! next advance/inspect request, and returns it as JSON tagged by type.
+//! `POST /notice`, `/voucher`, `/report` and `/exception` each deserialize
+//! the corresponding struct and emit it on the rollup device. `/notice` and
+//! `/voucher` return the produced output index; `/report` and `/exception`
+//! return `null`, as the device assigns them no index.
+//!
+//! The rollup protocol is strictly sequential (finish → blocking read →
+//! write), and the local [`OutputsMerkle`] must see notice/voucher leaves
+//! in the same order the device assigns their indices. So `fd` and
+//! `outputs_merkle` live behind a single [`Mutex`], and every handler holds
+//! it for its whole device interaction instead of just the pieces that
+//! touch the tree.
+
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::post;
+use axum::{Json, Router};
+use tokio::sync::Mutex;
+
+use super::merkle::OutputsMerkle;
+use super::{
+    handle_rollup_requests, perform_rollup_finish_request, rollup_throw_exception,
+    rollup_write_notice, rollup_write_report, rollup_write_voucher, Exception, FinishRequest,
+    Notice, Report, RollupFd, RollupRequest, Voucher,
+};
+
+struct RollupState {
+    fd: RollupFd,
+    outputs_merkle: OutputsMerkle,
+}
+
+type AppState = Mutex<RollupState>;
+
+type ApiError = (StatusCode, String);
+
+fn internal_error(err: impl std::fmt::Display) -> ApiError {
+    (StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+}
+
+/// Builds the router exposing the rollup HTTP loop on `fd`.
+pub fn router(fd: RollupFd) -> Router {
+    let state = Arc::new(Mutex::new(RollupState {
+        fd,
+        outputs_merkle: OutputsMerkle::new(),
+    }));
+
+    Router::new()
+        .route("/finish", post(finish))
+        .route("/notice", post(notice))
+        .route("/voucher", post(voucher))
+        .route("/report", post(report))
+        .route("/exception", post(exception))
+        .with_state(state)
+}
+
+async fn finish(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<FinishRequest>,
+) -> Result<Json<RollupRequest>, ApiError> {
+    let accept = request.status == "accept";
+    let state = state.lock().await;
+    let finish_request = perform_rollup_finish_request(&state.fd, accept)
+        .await
+        .map_err(internal_error)?;
+    let rollup_request = handle_rollup_requests(&state.fd, finish_request)
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(rollup_request))
+}
+
+async fn notice(
+    State(state): State<Arc<AppState>>,
+    Json(mut notice): Json<Notice>,
+) -> Result<Json<u64>, ApiError> {
+    let mut state = state.lock().await;
+    let RollupState { fd, outputs_merkle } = &mut *state;
+    let index = rollup_write_notice(fd, &mut notice, outputs_merkle).map_err(internal_error)?;
+    Ok(Json(index))
+}
+
+async fn voucher(
+    State(state): State<Arc<AppState>>,
+    Json(mut voucher): Json<Voucher>,
+) -> Result<Json<u64>, ApiError> {
+    let mut state = state.lock().await;
+    let RollupState { fd, outputs_merkle } = &mut *state;
+    let index = rollup_write_voucher(fd, &mut voucher, outputs_merkle).map_err(internal_error)?;
+    Ok(Json(index))
+}
+
+async fn report(
+    State(state): State<Arc<AppState>>,
+    Json(report): Json<Report>,
+) -> Result<Json<()>, ApiError> {
+    let state = state.lock().await;
+    rollup_write_report(&state.fd, &report).map_err(internal_error)?;
+    Ok(Json(()))
+}
+
+async fn exception(
+    State(state): State<Arc<AppState>>,
+    Json(exception): Json<Exception>,
+) -> Result<Json<()>, ApiError> {
+    let state = state.lock().await;
+    rollup_throw_exception(&state.fd, &exception).map_err(internal_error)?;
+    Ok(Json(()))
+}
@@ -0,0 +1,210 @@
+// Copyright Cartesi and individual authors (see AUTHORS)
+// SPDX-License-Identifier: Apache-2.0
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//
+
+//! Off-line reconstruction of the outputs Merkle tree that notices and
+//! vouchers are proven against on-chain.
+//!
+//! Each emitted output becomes a leaf in a fixed-height binary tree, hashed
+//! with `keccak256`. Empty subtrees are filled in from a precomputed table
+//! of zero hashes, so the root and inclusion proofs can be produced without
+//! ever materializing the full tree.
+
+use lazy_static::lazy_static;
+use sha3::{Digest, Keccak256};
+
+use super::abi::{encode_function_call, AbiValue};
+
+/// Height of the outputs Merkle tree, matching the on-chain rollups
+/// `OutputsMerkle` contract.
+pub const TREE_HEIGHT: usize = 63;
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    out.copy_from_slice(&Keccak256::digest(data));
+    out
+}
+
+fn hash_pair(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+    keccak256(&[left, right].concat())
+}
+
+lazy_static! {
+    /// `ZERO_HASHES[i]` is the root of an empty subtree of height `i`.
+    static ref ZERO_HASHES: Vec<[u8; 32]> = {
+        let mut hashes = vec![[0u8; 32]; TREE_HEIGHT + 1];
+        for i in 1..=TREE_HEIGHT {
+            hashes[i] = hash_pair(hashes[i - 1], hashes[i - 1]);
+        }
+        hashes
+    };
+}
+
+/// Computes the keccak256 leaf hash of a notice, over its ABI-encoded
+/// `Notice(bytes)` form.
+pub fn notice_leaf_hash(payload: &[u8]) -> [u8; 32] {
+    let encoded = encode_function_call("Notice(bytes)", &[AbiValue::Bytes(payload.to_vec())])
+        .expect("Notice(bytes) arguments are always well-formed");
+    keccak256(&encoded)
+}
+
+/// Computes the keccak256 leaf hash of a voucher, over its ABI-encoded
+/// `Voucher(address,uint256,bytes)` form.
+pub fn voucher_leaf_hash(destination: &[u8; 20], value: &[u8; 32], payload: &[u8]) -> [u8; 32] {
+    let encoded = encode_function_call(
+        "Voucher(address,uint256,bytes)",
+        &[
+            AbiValue::Address(*destination),
+            AbiValue::Uint256(*value),
+            AbiValue::Bytes(payload.to_vec()),
+        ],
+    )
+    .expect("Voucher(address,uint256,bytes) arguments are always well-formed");
+    keccak256(&encoded)
+}
+
+/// An incrementally-built outputs Merkle tree: leaves are appended in
+/// emission order, and the root or an inclusion proof can be queried at
+/// any point.
+#[derive(Debug, Default, Clone)]
+pub struct OutputsMerkle {
+    leaves: Vec<[u8; 32]>,
+}
+
+impl OutputsMerkle {
+    pub fn new() -> Self {
+        OutputsMerkle { leaves: Vec::new() }
+    }
+
+    /// Appends a leaf hash and returns its index in the tree.
+    pub fn insert(&mut self, leaf: [u8; 32]) -> usize {
+        self.leaves.push(leaf);
+        self.leaves.len() - 1
+    }
+
+    /// The current Merkle root.
+    pub fn root(&self) -> [u8; 32] {
+        let mut level = self.leaves.clone();
+        for height in 0..TREE_HEIGHT {
+            level = next_level(&level, height);
+        }
+        level.first().copied().unwrap_or(ZERO_HASHES[TREE_HEIGHT])
+    }
+
+    /// The ordered sibling hashes from `index`'s leaf up to the root, or
+    /// `None` if `index` has not been inserted yet.
+    pub fn proof(&self, index: usize) -> Option<Vec<[u8; 32]>> {
+        if index >= self.leaves.len() {
+            return None;
+        }
+
+        let mut level = self.leaves.clone();
+        let mut idx = index;
+        let mut proof = Vec::with_capacity(TREE_HEIGHT);
+        for height in 0..TREE_HEIGHT {
+            let sibling_idx = idx ^ 1;
+            let sibling = level.get(sibling_idx).copied().unwrap_or(ZERO_HASHES[height]);
+            proof.push(sibling);
+            level = next_level(&level, height);
+            idx /= 2;
+        }
+        Some(proof)
+    }
+}
+
+fn next_level(level: &[[u8; 32]], height: usize) -> Vec<[u8; 32]> {
+    if level.is_empty() {
+        return Vec::new();
+    }
+    let mut next = Vec::with_capacity(level.len() / 2 + 1);
+    let mut i = 0;
+    while i < level.len() {
+        let left = level[i];
+        let right = level.get(i + 1).copied().unwrap_or(ZERO_HASHES[height]);
+        next.push(hash_pair(left, right));
+        i += 2;
+    }
+    next
+}
+
+/// Verifies an inclusion proof produced by [`OutputsMerkle::proof`] against
+/// a root.
+pub fn verify_proof(leaf: [u8; 32], index: usize, proof: &[[u8; 32]], root: [u8; 32]) -> bool {
+    let mut hash = leaf;
+    let mut idx = index;
+    for sibling in proof {
+        hash = if idx % 2 == 0 {
+            hash_pair(hash, *sibling)
+        } else {
+            hash_pair(*sibling, hash)
+        };
+        idx /= 2;
+    }
+    hash == root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_tree_root_is_the_top_zero_hash() {
+        let tree = OutputsMerkle::new();
+        assert_eq!(tree.root(), ZERO_HASHES[TREE_HEIGHT]);
+    }
+
+    #[test]
+    fn single_leaf_proof_verifies_against_root() {
+        let mut tree = OutputsMerkle::new();
+        let leaf = notice_leaf_hash(b"hello");
+        let index = tree.insert(leaf);
+
+        let proof = tree.proof(index).unwrap();
+        assert!(verify_proof(leaf, index, &proof, tree.root()));
+    }
+
+    #[test]
+    fn every_leaf_in_a_multi_leaf_tree_proves_against_the_same_root() {
+        let mut tree = OutputsMerkle::new();
+        let leaves: Vec<[u8; 32]> = (0..5u8).map(|i| notice_leaf_hash(&[i])).collect();
+        for leaf in &leaves {
+            tree.insert(*leaf);
+        }
+
+        let root = tree.root();
+        for (index, leaf) in leaves.iter().enumerate() {
+            let proof = tree.proof(index).unwrap();
+            assert!(verify_proof(*leaf, index, &proof, root));
+        }
+    }
+
+    #[test]
+    fn proof_fails_against_the_wrong_leaf() {
+        let mut tree = OutputsMerkle::new();
+        let leaf_a = notice_leaf_hash(b"a");
+        let leaf_b = notice_leaf_hash(b"b");
+        tree.insert(leaf_a);
+        let index_b = tree.insert(leaf_b);
+
+        let proof = tree.proof(index_b).unwrap();
+        assert!(!verify_proof(leaf_a, index_b, &proof, tree.root()));
+    }
+
+    #[test]
+    fn proof_is_none_for_an_out_of_range_index() {
+        let tree = OutputsMerkle::new();
+        assert!(tree.proof(0).is_none());
+    }
+}
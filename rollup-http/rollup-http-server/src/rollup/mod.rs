@@ -23,8 +23,15 @@ use std::io::ErrorKind;
 use libc::c_void;
 use serde::{Deserialize, Serialize};
 use validator::Validate;
-use regex::Regex;
-use lazy_static::lazy_static;
+
+pub mod abi;
+pub mod eth;
+#[cfg(feature = "http-server")]
+pub mod http;
+pub mod merkle;
+
+use eth::{Address, U256};
+use merkle::{notice_leaf_hash, voucher_leaf_hash, OutputsMerkle};
 
 include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
 
@@ -61,11 +68,6 @@ pub const REQUEST_TYPE_ADVANCE_STATE: u32 = 0;
 pub const REQUEST_TYPE_INSPECT_STATE: u32 = 1;
 pub const CARTESI_ROLLUP_ADDRESS_SIZE: u32 = 20;
 
-lazy_static! {
-    static ref ETH_ADDR_REGEXP: Regex = Regex::new(r"0x[0-9a-fA-F]{1,42}$").unwrap();
-    static ref ETH_U256_REGEXP: Regex = Regex::new(r"0x[0-9a-fA-F]{1,64}$").unwrap();
-}
-
 #[derive(Debug, Default)]
 pub struct RollupError {
     message: String,
@@ -116,7 +118,7 @@ impl From<&mut RollupFinish> for cmt_rollup_finish_t {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AdvanceMetadata {
-    pub msg_sender: String,
+    pub msg_sender: Address,
     pub input_index: u64,
     pub block_number: u64,
     pub block_timestamp: u64,
@@ -124,13 +126,11 @@ pub struct AdvanceMetadata {
 
 impl From<cmt_rollup_advance_t> for AdvanceMetadata {
     fn from(other: cmt_rollup_advance_t) -> Self {
-        let mut address = "0x".to_string();
-        address.push_str(&hex::encode(&other.msg_sender));
         AdvanceMetadata {
             input_index: other.index,
             block_timestamp: other.block_timestamp,
             block_number: other.block_number,
-            msg_sender: address,
+            msg_sender: Address(other.msg_sender),
         }
     }
 }
@@ -156,6 +156,8 @@ pub struct InspectRequest {
     pub payload: String,
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "request_type", rename_all = "snake_case")]
 pub enum RollupRequest {
     Inspect(InspectRequest),
     Advance(AdvanceRequest),
@@ -178,10 +180,8 @@ pub struct Notice {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct Voucher {
-    #[validate(regex = "ETH_ADDR_REGEXP")]
-    pub destination: String,
-    #[validate(regex = "ETH_U256_REGEXP")]
-    pub value: String,
+    pub destination: Address,
+    pub value: U256,
     pub payload: String,
 }
 
@@ -313,6 +313,7 @@ pub fn rollup_read_inspect_state_request(
 pub fn rollup_write_notice(
     fd: &RollupFd,
     notice: &mut Notice,
+    outputs_merkle: &mut OutputsMerkle,
 ) -> Result<u64, Box<dyn std::error::Error>> {
     print_notice(notice);
 
@@ -346,6 +347,7 @@ pub fn rollup_write_notice(
         ))));
     } else {
         log::debug!("notice with id {} successfully written!", notice_index);
+        outputs_merkle.insert(notice_leaf_hash(&binary_payload));
     }
 
     Ok(notice_index as u64)
@@ -355,6 +357,7 @@ pub fn rollup_write_notice(
 pub fn rollup_write_voucher(
     fd: &RollupFd,
     voucher: &mut Voucher,
+    outputs_merkle: &mut OutputsMerkle,
 ) -> Result<u64, Box<dyn std::error::Error>> {
     print_voucher(voucher);
 
@@ -370,27 +373,8 @@ pub fn rollup_write_voucher(
     let payload_data = payload_buffer.as_mut_ptr();
     let payload_length = binary_payload.len();
 
-    let binary_value = match hex::decode(&voucher.value[2..]) {
-        Ok(data) => data,
-        Err(_err) => {
-            return Err(Box::new(RollupError::new(&format!(
-                "Error decoding voucher value, it must be in Ethereum hex binary format"
-            ))));
-        }
-    };
-    let mut value_buffer: Vec<u8> = Vec::with_capacity(binary_value.len());
-    let value_data = value_buffer.as_mut_ptr();
-    let value_length = binary_value.len();
-
-    let address_c = match hex::decode(&voucher.destination[2..]) {
-        Ok(res) => res,
-        Err(e) => {
-            return Err(Box::new(RollupError::new(&format!(
-                "address not valid: {}",
-                e
-            ))));
-        }
-    };
+    let mut value_buffer = *voucher.value.as_bytes();
+    let address_buffer = *voucher.destination.as_bytes();
 
     let mut voucher_index: std::os::raw::c_ulong = 0;
     let res = unsafe {
@@ -399,18 +383,13 @@ pub fn rollup_write_voucher(
             payload_buffer.as_mut_ptr(),
             binary_payload.len(),
         );
-        std::ptr::copy(
-            binary_value.as_ptr(),
-            value_buffer.as_mut_ptr(),
-            binary_value.len(),
-        );
 
         cmt_rollup_emit_voucher(
             fd.0,
-            address_c.len() as u32,
-            address_c.as_ptr() as *const c_void,
-            value_length as u32,
-            value_data as *mut c_void,
+            address_buffer.len() as u32,
+            address_buffer.as_ptr() as *const c_void,
+            value_buffer.len() as u32,
+            value_buffer.as_mut_ptr() as *mut c_void,
             payload_length as u32,
             payload_data as *mut c_void,
             &mut voucher_index,
@@ -424,6 +403,7 @@ pub fn rollup_write_voucher(
         ))));
     } else {
         log::debug!("voucher with id {} successfully written!", voucher_index);
+        outputs_merkle.insert(voucher_leaf_hash(&address_buffer, &value_buffer, &binary_payload));
     }
 
     Ok(voucher_index as u64)
@@ -575,12 +555,8 @@ pub async fn handle_rollup_requests(
     }
 }
 
-pub fn format_address_printout(address: &str, printout_address: &mut String) {
-    if address.starts_with("0x") {
-        printout_address.push_str(address);
-    } else {
-        printout_address.push_str(&format!("0x{}", address));
-    }
+pub fn format_address_printout(address: &Address, printout_address: &mut String) {
+    printout_address.push_str(&address.to_checksum());
 }
 
 pub fn print_advance(advance: &AdvanceRequest) {